@@ -1,3 +1,6 @@
+use std::collections::VecDeque;
+use std::io::{Cursor, Read};
+
 /***
  * Defines all of the tokens that can be produced by the lexer's `next_token` function.
  * These tokens define both the valid and invalid lexemes of an oso source file.
@@ -7,6 +10,27 @@ pub enum Token {
     EOF,
     Fn,
     Identifier(String),
+    Integer(i64),
+    Float(f64),
+    HexInt(u64),
+    StringLiteral(String),
+    Eq,
+    EqEq,
+    Bang,
+    BangEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Plus,
+    Minus,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Semicolon,
+    Comma,
+    Colon,
     Invalid,
 }
 
@@ -14,15 +38,43 @@ pub enum Token {
  * Defines all of the internal states of the lexer. Since the lexer is an NFA, transition functions
  * exist on each state for each possible input character.
  */
-enum States {
+#[derive(PartialEq, Clone, Copy)]
+pub(crate) enum States {
     Begin,
     Collect,
     Hex,
     Numeric,
     Number,
+    String,
+    StringEscape,
+    Operator,
     Resolve,
 }
 
+/***
+ * The set of characters that can begin a punctuation/operator lexeme.
+ */
+fn is_operator_start(c: char) -> bool {
+    matches!(c, '=' | '!' | '<' | '>' | '+' | '-' | '(' | ')' | '{' | '}' | ';' | ',' | ':')
+}
+
+/***
+ * Pushes `state` onto the lexer's state stack. Suspends the current scanning mode so a nested
+ * construct (e.g. an interpolated expression inside a string) can be scanned in its own mode and
+ * the caller can resume where it left off via `pop_state`.
+ */
+pub(crate) fn push_state(stack: &mut Vec<States>, state: States) {
+    stack.push(state);
+}
+
+/***
+ * Pops the most recently pushed state off of the lexer's state stack, resuming the mode that was
+ * suspended by the matching `push_state`.
+ */
+pub(crate) fn pop_state(stack: &mut Vec<States>) -> Option<States> {
+    stack.pop()
+}
+
 trait TokenResolver {
     fn resolve(&self) -> Token;
 }
@@ -36,43 +88,465 @@ impl TokenResolver for String {
     }
 }
 
+trait IntResolver {
+    fn resolve_int(&self) -> Token;
+}
+
+impl IntResolver for String {
+    fn resolve_int(&self) -> Token {
+        match self.parse::<i64>() {
+            Ok(value) => Token::Integer(value),
+            Err(_) => Token::Invalid,
+        }
+    }
+}
+
+trait HexResolver {
+    fn resolve_hex(&self) -> Token;
+}
+
+impl HexResolver for String {
+    fn resolve_hex(&self) -> Token {
+        match u64::from_str_radix(&self[2..], 16) {
+            Ok(value) => Token::HexInt(value),
+            Err(_) => Token::Invalid,
+        }
+    }
+}
+
+trait FloatResolver {
+    fn resolve_float(&self) -> Token;
+}
+
+impl FloatResolver for String {
+    fn resolve_float(&self) -> Token {
+        match self.parse::<f64>() {
+            Ok(value) => Token::Float(value),
+            Err(_) => Token::Invalid,
+        }
+    }
+}
+
+trait OperatorResolver {
+    fn resolve_operator(&self) -> Token;
+}
+
+impl OperatorResolver for String {
+    fn resolve_operator(&self) -> Token {
+        match self.as_str() {
+            "=" => Token::Eq,
+            "==" => Token::EqEq,
+            "!" => Token::Bang,
+            "!=" => Token::BangEq,
+            "<" => Token::Lt,
+            "<=" => Token::LtEq,
+            ">" => Token::Gt,
+            ">=" => Token::GtEq,
+            "+" => Token::Plus,
+            "-" => Token::Minus,
+            "(" => Token::LParen,
+            ")" => Token::RParen,
+            "{" => Token::LBrace,
+            "}" => Token::RBrace,
+            ";" => Token::Semicolon,
+            "," => Token::Comma,
+            ":" => Token::Colon,
+            _ => Token::Invalid,
+        }
+    }
+}
+
+/***
+ * A position within a source file, tracked as both a flat character offset and a line/col pair
+ * so diagnostics can point at either representation. The one exception is the `end` of a
+ * `Token::Invalid` produced for undecodable UTF-8 input, whose `offset` is the byte offset of
+ * the offending sequence in the stream.
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Pos {
+    pub offset: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn start() -> Pos {
+        Pos { offset: 0, line: 1, col: 1 }
+    }
+}
+
+/***
+ * Wraps a value with the source span it was scanned from, so callers can report diagnostics
+ * against the original lexeme rather than just its resolved value.
+ */
+#[derive(Debug, PartialEq)]
+pub struct Spanned<T> {
+    pub value: T,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+/***
+ * Advances `pos` past `c`, bumping the line and resetting the column on newlines.
+ */
+fn advance(pos: &mut Pos, c: char) {
+    pos.offset += 1;
+    if c == '\n' {
+        pos.line += 1;
+        pos.col = 1;
+    } else {
+        pos.col += 1;
+    }
+}
+
+/***
+ * Produces `char`s with a small amount of lookahead, so the NFA can peek ahead without requiring
+ * the whole input to be materialized as a `Vec<char>` up front.
+ */
+pub trait CharSource {
+    fn peek(&mut self, lookahead: usize) -> Option<char>;
+    fn bump(&mut self) -> Option<char>;
+
+    /***
+     * Takes the byte offset of the first invalid UTF-8 sequence encountered, if any, clearing it
+     * so the source reports clean EOF on every call after the first. Sources that can never fail
+     * to decode (e.g. one backed by an already-validated `Vec<char>`) can rely on the default of
+     * `None`.
+     */
+    fn take_invalid_offset(&mut self) -> Option<usize> {
+        None
+    }
+}
+
+/***
+ * Decodes UTF-8 directly from an `io::Read`, pulling only as many bytes as the lexer's lookahead
+ * needs rather than reading the whole stream into memory up front. Mirrors Enso's lazy-reader:
+ * callers drive decoding one scalar value at a time via `peek`/`bump`.
+ */
+pub struct ReadCharSource<R: Read> {
+    reader: R,
+    lookahead: VecDeque<char>,
+    byte_offset: usize,
+    invalid_offset: Option<usize>,
+    at_eof: bool,
+    consumed: usize,
+}
+
+impl<R: Read> ReadCharSource<R> {
+    pub fn new(reader: R) -> ReadCharSource<R> {
+        ReadCharSource {
+            reader,
+            lookahead: VecDeque::new(),
+            byte_offset: 0,
+            invalid_offset: None,
+            at_eof: false,
+            consumed: 0,
+        }
+    }
+
+    /***
+     * The number of `char`s consumed via `bump` so far.
+     */
+    pub fn consumed(&self) -> usize {
+        self.consumed
+    }
+
+    fn fill(&mut self, lookahead: usize) {
+        while self.lookahead.len() <= lookahead && !self.at_eof {
+            match self.decode_one() {
+                Some(c) => self.lookahead.push_back(c),
+                None => self.at_eof = true,
+            }
+        }
+    }
+
+    /***
+     * Reads one byte at a time from `reader` until a complete (or invalid) UTF-8 sequence is
+     * available.
+     */
+    fn decode_one(&mut self) -> Option<char> {
+        let mut buf = [0u8; 4];
+        let mut len = 0;
+        loop {
+            let mut byte = [0u8; 1];
+            match self.reader.read(&mut byte) {
+                Ok(0) => return if len == 0 { None } else { self.mark_invalid() },
+                Ok(_) => {
+                    buf[len] = byte[0];
+                    len += 1;
+                    match std::str::from_utf8(&buf[..len]) {
+                        Ok(decoded) => {
+                            self.byte_offset += len;
+                            return decoded.chars().next();
+                        }
+                        Err(error) if error.error_len().is_some() => return self.mark_invalid(),
+                        Err(_) => continue,
+                    }
+                }
+                Err(_) => return self.mark_invalid(),
+            }
+        }
+    }
+
+    fn mark_invalid(&mut self) -> Option<char> {
+        self.invalid_offset.get_or_insert(self.byte_offset);
+        self.at_eof = true;
+        None
+    }
+}
+
+impl<R: Read> CharSource for ReadCharSource<R> {
+    fn peek(&mut self, lookahead: usize) -> Option<char> {
+        self.fill(lookahead);
+        self.lookahead.get(lookahead).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        self.fill(0);
+        let c = self.lookahead.pop_front();
+        if c.is_some() {
+            self.consumed += 1;
+        }
+        c
+    }
+
+    fn take_invalid_offset(&mut self) -> Option<usize> {
+        self.invalid_offset.take()
+    }
+}
+
 /***
  * Scans a vector of characters that represents a source file for the first lexeme and returns the
  * result.
  */
 pub fn next_token(input: &Vec<char>, current_ptr: &mut usize) -> Token {
+    next_spanned_token(input, current_ptr, &mut Pos::start()).value
+}
+
+/***
+ * Scans a vector of characters that represents a source file for the first lexeme and returns it
+ * together with the `Pos` span it was scanned from. `pos` is advanced in lockstep with
+ * `current_ptr` so callers can keep reusing it across successive calls. Internally this is a thin
+ * wrapper over an in-memory `ReadCharSource` so the `Vec<char>` API keeps working unchanged.
+ */
+pub fn next_spanned_token(input: &Vec<char>, current_ptr: &mut usize, pos: &mut Pos) -> Spanned<Token> {
+    let remaining: String = input[*current_ptr..].iter().collect();
+    let mut source = ReadCharSource::new(Cursor::new(remaining.into_bytes()));
+    let result = scan(&mut source, pos, States::Begin, &mut Vec::new());
+    *current_ptr += source.consumed();
+    result
+}
+
+/***
+ * Drives the NFA from `initial_state`. A `}` seen in the `Begin` state while `state_stack` is
+ * non-empty pops the mode suspended by `push_state` and resumes scanning in it.
+ */
+fn scan(
+    source: &mut dyn CharSource,
+    pos: &mut Pos,
+    initial_state: States,
+    state_stack: &mut Vec<States>,
+) -> Spanned<Token> {
     let mut result = Token::EOF;
-    let mut current_state = States::Begin;
+    let mut current_state = initial_state;
     let mut collected = String::from("");
-    while *current_ptr < input.len() {
+    let mut start = *pos;
+    while let Some(c) = source.peek(0) {
         match current_state {
             States::Begin => {
-                if input[*current_ptr].is_alphabetic() {
+                if c.is_alphabetic() {
                     current_state = States::Collect;
-                    collected.push(input[*current_ptr]);
-                } else if input[*current_ptr].is_numeric() {
+                    start = *pos;
+                    collected.push(c);
+                } else if c.is_numeric() {
                     current_state = States::Numeric;
-                    collected.push(input[*current_ptr]);
+                    start = *pos;
+                    collected.push(c);
+                } else if c == '"' {
+                    current_state = States::String;
+                    start = *pos;
+                } else if c == '}' && !state_stack.is_empty() {
+                    current_state = pop_state(state_stack).unwrap();
+                    start = *pos;
+                } else if is_operator_start(c) {
+                    current_state = States::Operator;
+                    start = *pos;
+                    collected.push(c);
                 }
-                *current_ptr += 1;
+                advance(pos, c);
+                source.bump();
             }
             States::Collect => {
-                if input[*current_ptr].is_alphanumeric() {
-                    collected.push(input[*current_ptr]);
-                    *current_ptr += 1;
+                if c.is_alphanumeric() {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
                 } else {
                     current_state = States::Resolve;
                 }
             }
-            States::Hex => { /* FIXME */ }
-            States::Numeric => { /* FIXME */ }
+            States::Hex => {
+                if c.is_ascii_hexdigit() {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                } else if c.is_alphabetic() {
+                    return Spanned { value: Token::Invalid, start, end: *pos };
+                } else {
+                    return Spanned { value: collected.resolve_hex(), start, end: *pos };
+                }
+            }
+            States::Numeric => {
+                if collected == "0" && (c == 'x' || c == 'X') {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::Hex;
+                } else if c.is_numeric() {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                } else if c == '.' && source.peek(1).is_some_and(|n| n.is_numeric()) {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::Number;
+                } else if c.is_alphabetic() {
+                    return Spanned { value: Token::Invalid, start, end: *pos };
+                } else {
+                    return Spanned { value: collected.resolve_int(), start, end: *pos };
+                }
+            }
             States::Number => {
-                if input[*current_ptr].is_numeric() {
-                    collected.push(input[*current_ptr]);
-                } else if input[*current_ptr].is_alphabetic() {
-                    return Token::Invalid;
+                if c.is_numeric() {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                } else if c == 'e' || c == 'E' {
+                    let mut lookahead = 1;
+                    if source.peek(lookahead).is_some_and(|n| n == '+' || n == '-') {
+                        lookahead += 1;
+                    }
+                    if source.peek(lookahead).is_some_and(|n| n.is_numeric()) {
+                        collected.push(c);
+                        advance(pos, c);
+                        source.bump();
+                        if let Some(sign) = source.peek(0) {
+                            if sign == '+' || sign == '-' {
+                                collected.push(sign);
+                                advance(pos, sign);
+                                source.bump();
+                            }
+                        }
+                    } else {
+                        return Spanned { value: Token::Invalid, start, end: *pos };
+                    }
+                } else if c.is_alphabetic() {
+                    return Spanned { value: Token::Invalid, start, end: *pos };
+                } else {
+                    return Spanned { value: collected.resolve_float(), start, end: *pos };
+                }
+            }
+            States::String => {
+                if c == '"' {
+                    advance(pos, c);
+                    source.bump();
+                    return Spanned { value: Token::StringLiteral(collected), start, end: *pos };
+                } else if c == '\\' {
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::StringEscape;
+                } else if c == '$' && source.peek(1) == Some('{') {
+                    advance(pos, c);
+                    source.bump();
+                    advance(pos, '{');
+                    source.bump();
+                    push_state(state_stack, States::String);
+                    return Spanned { value: Token::StringLiteral(collected), start, end: *pos };
+                } else {
+                    collected.push(c);
+                    advance(pos, c);
+                    source.bump();
+                }
+            }
+            States::StringEscape => {
+                if c == 'n' {
+                    collected.push('\n');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == 't' {
+                    collected.push('\t');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == 'r' {
+                    collected.push('\r');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == '\\' {
+                    collected.push('\\');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == '"' {
+                    collected.push('"');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == '0' {
+                    collected.push('\0');
+                    advance(pos, c);
+                    source.bump();
+                    current_state = States::String;
+                } else if c == 'u' {
+                    advance(pos, c);
+                    source.bump();
+                    match source.peek(0) {
+                        Some('{') => {
+                            advance(pos, '{');
+                            source.bump();
+                        }
+                        _ => return Spanned { value: Token::Invalid, start, end: *pos },
+                    }
+                    let mut hex = String::from("");
+                    while let Some(h) = source.peek(0) {
+                        if h == '}' {
+                            break;
+                        }
+                        hex.push(h);
+                        advance(pos, h);
+                        source.bump();
+                    }
+                    match source.peek(0) {
+                        Some('}') => {
+                            advance(pos, '}');
+                            source.bump();
+                        }
+                        _ => return Spanned { value: Token::Invalid, start, end: *pos },
+                    }
+                    match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        Some(decoded) => {
+                            collected.push(decoded);
+                            current_state = States::String;
+                        }
+                        None => return Spanned { value: Token::Invalid, start, end: *pos },
+                    }
+                } else {
+                    return Spanned { value: Token::Invalid, start, end: *pos };
+                }
+            }
+            States::Operator => {
+                let mut candidate = collected.clone();
+                candidate.push(c);
+                if candidate.resolve_operator() != Token::Invalid {
+                    advance(pos, c);
+                    source.bump();
+                    return Spanned { value: candidate.resolve_operator(), start, end: *pos };
+                } else {
+                    return Spanned { value: collected.resolve_operator(), start, end: *pos };
                 }
-                *current_ptr += 1;
             }
             States::Resolve => {
                 result = collected.resolve();
@@ -80,10 +554,127 @@ pub fn next_token(input: &Vec<char>, current_ptr: &mut usize) -> Token {
             }
         }
     }
-    if collected.len() > 0 {
-        result = collected.resolve();
+    if let Some(offset) = source.take_invalid_offset() {
+        return Spanned {
+            value: Token::Invalid,
+            start,
+            end: Pos { offset, line: pos.line, col: pos.col },
+        };
+    }
+    if current_state == States::String || current_state == States::StringEscape {
+        result = Token::Invalid;
+    } else if collected.len() > 0 {
+        result = match current_state {
+            States::Numeric => collected.resolve_int(),
+            States::Hex => collected.resolve_hex(),
+            States::Number => collected.resolve_float(),
+            States::Operator => collected.resolve_operator(),
+            _ => collected.resolve(),
+        };
+    }
+    Spanned { value: result, start, end: *pos }
+}
+
+/***
+ * Owns the source being lexed and drives `scan` to completion, hiding the offset/`Pos`
+ * bookkeeping that callers of `next_spanned_token` would otherwise have to manage by hand.
+ */
+pub struct Lexer<R: Read> {
+    source: ReadCharSource<R>,
+    pos: Pos,
+    state_stack: Vec<States>,
+}
+
+impl Lexer<Cursor<Vec<u8>>> {
+    pub fn new(input: Vec<char>) -> Lexer<Cursor<Vec<u8>>> {
+        let bytes: String = input.into_iter().collect();
+        Lexer::from_reader(Cursor::new(bytes.into_bytes()))
+    }
+}
+
+impl<R: Read> Lexer<R> {
+    /***
+     * Builds a lexer directly over a streaming `io::Read` source, decoding UTF-8 incrementally
+     * rather than requiring the whole input to be materialized up front.
+     */
+    pub fn from_reader(reader: R) -> Lexer<R> {
+        Lexer { source: ReadCharSource::new(reader), pos: Pos::start(), state_stack: Vec::new() }
+    }
+
+    /***
+     * Advances past any whitespace, `//` line comments, and nested `/* */` block comments so
+     * they never reach the token stream.
+     */
+    fn skip_trivia(&mut self) {
+        loop {
+            while self.source.peek(0).is_some_and(|c| c.is_whitespace()) {
+                let c = self.source.bump().unwrap();
+                advance(&mut self.pos, c);
+            }
+            if self.starts_with("//") {
+                while self.source.peek(0).is_some_and(|c| c != '\n') {
+                    let c = self.source.bump().unwrap();
+                    advance(&mut self.pos, c);
+                }
+                continue;
+            }
+            if self.starts_with("/*") {
+                self.consume(2);
+                let mut depth = 1;
+                while depth > 0 && self.source.peek(0).is_some() {
+                    if self.starts_with("/*") {
+                        depth += 1;
+                        self.consume(2);
+                    } else if self.starts_with("*/") {
+                        depth -= 1;
+                        self.consume(2);
+                    } else {
+                        self.consume(1);
+                    }
+                }
+                continue;
+            }
+            break;
+        }
+    }
+
+    fn starts_with(&mut self, prefix: &str) -> bool {
+        prefix.chars().enumerate().all(|(offset, c)| self.source.peek(offset) == Some(c))
+    }
+
+    fn consume(&mut self, count: usize) {
+        for _ in 0..count {
+            match self.source.bump() {
+                Some(c) => advance(&mut self.pos, c),
+                None => break,
+            }
+        }
+    }
+
+    /***
+     * Skips whitespace and comments, then scans and returns the next lexeme.
+     */
+    pub fn next_token(&mut self) -> Spanned<Token> {
+        self.skip_trivia();
+        scan(&mut self.source, &mut self.pos, States::Begin, &mut self.state_stack)
+    }
+
+    /***
+     * Drives the lexer to completion, returning every lexeme (whitespace and comments already
+     * stripped) terminated by a trailing `Token::EOF`.
+     */
+    pub fn tokenize(&mut self) -> Vec<Spanned<Token>> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = self.next_token();
+            let reached_eof = token.value == Token::EOF;
+            tokens.push(token);
+            if reached_eof {
+                break;
+            }
+        }
+        tokens
     }
-    result
 }
 
 #[cfg(test)]
@@ -139,4 +730,241 @@ mod tests {
         let result = next_token(&to_chars("\nfn"), &mut 0);
         assert_eq_pretty!(Token::Fn, result);
     }
+
+    #[test]
+    fn integer_test1() {
+        let result = next_token(&to_chars("0"), &mut 0);
+        assert_eq_pretty!(Token::Integer(0), result);
+    }
+
+    #[test]
+    fn integer_test2() {
+        let result = next_token(&to_chars("12345"), &mut 0);
+        assert_eq_pretty!(Token::Integer(12345), result);
+    }
+
+    #[test]
+    fn integer_test3() {
+        let result = next_token(&to_chars("123."), &mut 0);
+        assert_eq_pretty!(Token::Integer(123), result);
+    }
+
+    #[test]
+    fn integer_adjacent_letter_test1() {
+        let result = next_token(&to_chars("123abc"), &mut 0);
+        assert_eq_pretty!(Token::Invalid, result);
+    }
+
+    #[test]
+    fn float_test1() {
+        let result = next_token(&to_chars("1.5"), &mut 0);
+        assert_eq_pretty!(Token::Float(1.5), result);
+    }
+
+    #[test]
+    fn float_test2() {
+        let result = next_token(&to_chars("1.5e10"), &mut 0);
+        assert_eq_pretty!(Token::Float(1.5e10), result);
+    }
+
+    #[test]
+    fn float_test3() {
+        let result = next_token(&to_chars("1.5e-10"), &mut 0);
+        assert_eq_pretty!(Token::Float(1.5e-10), result);
+    }
+
+    #[test]
+    fn float_invalid_exponent_test1() {
+        let result = next_token(&to_chars("1.5e"), &mut 0);
+        assert_eq_pretty!(Token::Invalid, result);
+    }
+
+    #[test]
+    fn hex_test1() {
+        let result = next_token(&to_chars("0x1A"), &mut 0);
+        assert_eq_pretty!(Token::HexInt(26), result);
+    }
+
+    #[test]
+    fn hex_test2() {
+        let result = next_token(&to_chars("0xFF "), &mut 0);
+        assert_eq_pretty!(Token::HexInt(255), result);
+    }
+
+    #[test]
+    fn hex_empty_test1() {
+        let result = next_token(&to_chars("0x"), &mut 0);
+        assert_eq_pretty!(Token::Invalid, result);
+    }
+
+    #[test]
+    fn spanned_test1() {
+        let result = next_spanned_token(&to_chars("fn"), &mut 0, &mut Pos::start());
+        assert_eq_pretty!(Token::Fn, result.value);
+        assert_eq_pretty!(Pos { offset: 0, line: 1, col: 1 }, result.start);
+        assert_eq_pretty!(Pos { offset: 2, line: 1, col: 3 }, result.end);
+    }
+
+    #[test]
+    fn spanned_test2() {
+        let current_ptr = &mut 0;
+        let pos = &mut Pos::start();
+        let input = &to_chars("  fn");
+        let result = next_spanned_token(input, current_ptr, pos);
+        assert_eq_pretty!(Token::Fn, result.value);
+        assert_eq_pretty!(Pos { offset: 2, line: 1, col: 3 }, result.start);
+    }
+
+    #[test]
+    fn spanned_test3() {
+        let current_ptr = &mut 0;
+        let pos = &mut Pos::start();
+        let input = &to_chars("fn\nfn");
+        next_spanned_token(input, current_ptr, pos);
+        let result = next_spanned_token(input, current_ptr, pos);
+        assert_eq_pretty!(Token::Fn, result.value);
+        assert_eq_pretty!(Pos { offset: 3, line: 2, col: 1 }, result.start);
+    }
+
+    #[test]
+    fn lexer_tokenize_test1() {
+        let tokens = Lexer::new(to_chars("")).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(vec![&Token::EOF], values);
+    }
+
+    #[test]
+    fn lexer_tokenize_test2() {
+        let tokens = Lexer::new(to_chars("fn foo")).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(
+            vec![&Token::Fn, &Token::Identifier(String::from("foo")), &Token::EOF],
+            values
+        );
+    }
+
+    #[test]
+    fn lexer_skips_line_comment_test1() {
+        let tokens = Lexer::new(to_chars("fn // a comment\nfoo")).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(
+            vec![&Token::Fn, &Token::Identifier(String::from("foo")), &Token::EOF],
+            values
+        );
+    }
+
+    #[test]
+    fn string_test1() {
+        let result = next_token(&to_chars("\"hello\""), &mut 0);
+        assert_eq_pretty!(Token::StringLiteral(String::from("hello")), result);
+    }
+
+    #[test]
+    fn string_escape_test1() {
+        let result = next_token(&to_chars("\"a\\nb\\t\\\"c\""), &mut 0);
+        assert_eq_pretty!(Token::StringLiteral(String::from("a\nb\t\"c")), result);
+    }
+
+    #[test]
+    fn string_unicode_escape_test1() {
+        let result = next_token(&to_chars("\"\\u{48}\\u{49}\""), &mut 0);
+        assert_eq_pretty!(Token::StringLiteral(String::from("HI")), result);
+    }
+
+    #[test]
+    fn string_unterminated_test1() {
+        let result = next_token(&to_chars("\"abc"), &mut 0);
+        assert_eq_pretty!(Token::Invalid, result);
+    }
+
+    #[test]
+    fn string_unknown_escape_test1() {
+        let result = next_token(&to_chars("\"\\q\""), &mut 0);
+        assert_eq_pretty!(Token::Invalid, result);
+    }
+
+    #[test]
+    fn lexer_string_interpolation_test1() {
+        let tokens = Lexer::new(to_chars("\"a${fn}b\"")).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(
+            vec![
+                &Token::StringLiteral(String::from("a")),
+                &Token::Fn,
+                &Token::StringLiteral(String::from("b")),
+                &Token::EOF
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn operator_test1() {
+        let result = next_token(&to_chars("="), &mut 0);
+        assert_eq_pretty!(Token::Eq, result);
+    }
+
+    #[test]
+    fn operator_maximal_munch_test1() {
+        let result = next_token(&to_chars("=="), &mut 0);
+        assert_eq_pretty!(Token::EqEq, result);
+    }
+
+    #[test]
+    fn operator_maximal_munch_test2() {
+        let result = next_token(&to_chars(">= "), &mut 0);
+        assert_eq_pretty!(Token::GtEq, result);
+    }
+
+    #[test]
+    fn operator_single_char_not_munched_test1() {
+        let result = next_token(&to_chars("+1"), &mut 0);
+        assert_eq_pretty!(Token::Plus, result);
+    }
+
+    #[test]
+    fn operator_punctuation_test1() {
+        let current_ptr = &mut 0;
+        let input = &to_chars("(x, y);");
+        assert_eq_pretty!(Token::LParen, next_token(input, current_ptr));
+        assert_eq_pretty!(Token::Identifier(String::from("x")), next_token(input, current_ptr));
+        assert_eq_pretty!(Token::Comma, next_token(input, current_ptr));
+        assert_eq_pretty!(Token::Identifier(String::from("y")), next_token(input, current_ptr));
+        assert_eq_pretty!(Token::RParen, next_token(input, current_ptr));
+        assert_eq_pretty!(Token::Semicolon, next_token(input, current_ptr));
+    }
+
+    #[test]
+    fn lexer_skips_nested_block_comment_test1() {
+        let tokens = Lexer::new(to_chars("fn /* outer /* inner */ still outer */ foo")).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(
+            vec![&Token::Fn, &Token::Identifier(String::from("foo")), &Token::EOF],
+            values
+        );
+    }
+
+    #[test]
+    fn lexer_from_reader_streams_utf8_test1() {
+        let tokens = Lexer::from_reader(Cursor::new(b"fn \xc3\xa9".to_vec())).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(
+            vec![&Token::Fn, &Token::Identifier(String::from("\u{e9}")), &Token::EOF],
+            values
+        );
+    }
+
+    #[test]
+    fn lexer_from_reader_invalid_utf8_test1() {
+        let tokens = Lexer::from_reader(Cursor::new(b"fn \xff".to_vec())).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(vec![&Token::Fn, &Token::Invalid, &Token::EOF], values);
+    }
+
+    #[test]
+    fn lexer_from_reader_truncated_utf8_test1() {
+        let tokens = Lexer::from_reader(Cursor::new(b"fn \xc3".to_vec())).tokenize();
+        let values: Vec<&Token> = tokens.iter().map(|t| &t.value).collect();
+        assert_eq_pretty!(vec![&Token::Fn, &Token::Invalid, &Token::EOF], values);
+    }
 }